@@ -0,0 +1,111 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension, Result};
+
+/// Wraps the SQLite connection used to persist which notification
+/// threads we've already alerted on, replacing the old comma-joined
+/// flat file.
+pub struct DbCtx {
+    conn: Connection,
+}
+
+impl DbCtx {
+    /// Opens (creating if necessary) the database at `path` and ensures
+    /// the `notifications` table exists.
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS notifications (
+                id             TEXT PRIMARY KEY,
+                updated_at     TEXT NOT NULL,
+                reason         TEXT NOT NULL,
+                title          TEXT NOT NULL,
+                first_seen     TEXT NOT NULL,
+                last_notified  TEXT NOT NULL
+            )",
+            [],
+        )?;
+        Ok(DbCtx { conn })
+    }
+
+    /// Returns `true` if we've already notified about this thread at
+    /// this (or a later) `updated_at` value, meaning it shouldn't fire
+    /// again.
+    pub fn seen(&self, id: &str, updated_at: &str) -> Result<bool> {
+        let stored_updated_at: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT updated_at FROM notifications WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(match stored_updated_at {
+            Some(stored) => stored.as_str() >= updated_at,
+            None => false,
+        })
+    }
+
+    /// Records that we've notified about a thread, inserting a fresh row
+    /// or updating the existing one (advancing `updated_at`/`last_notified`
+    /// and refreshing `reason`/`title`).
+    pub fn record(&self, id: &str, updated_at: &str, reason: &str, title: &str, now: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO notifications (id, updated_at, reason, title, first_seen, last_notified)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+             ON CONFLICT(id) DO UPDATE SET
+                updated_at = excluded.updated_at,
+                reason = excluded.reason,
+                title = excluded.title,
+                last_notified = excluded.last_notified",
+            params![id, updated_at, reason, title, now],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn in_memory_db() -> DbCtx {
+        DbCtx::open(Path::new(":memory:")).unwrap()
+    }
+
+    #[test]
+    fn unseen_thread_is_not_seen() {
+        let db = in_memory_db();
+        assert!(!db.seen("1", "2024-01-01T00:00:00Z").unwrap());
+    }
+
+    #[test]
+    fn recorded_thread_is_seen_at_the_same_updated_at() {
+        let db = in_memory_db();
+        db.record("1", "2024-01-01T00:00:00Z", "mention", "some title", "1000").unwrap();
+        assert!(db.seen("1", "2024-01-01T00:00:00Z").unwrap());
+    }
+
+    #[test]
+    fn thread_is_seen_when_updated_at_has_not_advanced() {
+        let db = in_memory_db();
+        db.record("1", "2024-01-02T00:00:00Z", "mention", "some title", "1000").unwrap();
+        assert!(db.seen("1", "2024-01-01T00:00:00Z").unwrap());
+    }
+
+    #[test]
+    fn thread_is_not_seen_once_updated_at_advances() {
+        let db = in_memory_db();
+        db.record("1", "2024-01-01T00:00:00Z", "mention", "some title", "1000").unwrap();
+        assert!(!db.seen("1", "2024-01-02T00:00:00Z").unwrap());
+    }
+
+    #[test]
+    fn record_twice_advances_updated_at_instead_of_erroring() {
+        let db = in_memory_db();
+        db.record("1", "2024-01-01T00:00:00Z", "mention", "first title", "1000").unwrap();
+        db.record("1", "2024-01-02T00:00:00Z", "review_requested", "second title", "2000").unwrap();
+        assert!(db.seen("1", "2024-01-02T00:00:00Z").unwrap());
+        assert!(!db.seen("1", "2024-01-03T00:00:00Z").unwrap());
+    }
+}
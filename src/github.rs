@@ -0,0 +1,98 @@
+use reqwest::header::{ACCEPT, AUTHORIZATION, USER_AGENT};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+
+const NOTIFICATIONS_URL: &str = "https://api.github.com/notifications";
+
+#[derive(Deserialize, Debug)]
+pub struct ThreadSubscription {
+    pub subscribed: bool,
+    pub ignored: bool,
+    pub reason: Option<String>,
+}
+
+/// Everything that can go wrong calling the notifications API from here:
+/// a network-level failure, or a non-2xx response (bad/expired token,
+/// unknown thread id, ...).
+#[derive(Debug)]
+pub enum GithubError {
+    Request(reqwest::Error),
+    Status(StatusCode, String),
+}
+
+impl std::fmt::Display for GithubError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GithubError::Request(err) => write!(f, "{err}"),
+            GithubError::Status(status, detail) => write!(f, "{status} {detail}"),
+        }
+    }
+}
+
+impl From<reqwest::Error> for GithubError {
+    fn from(err: reqwest::Error) -> Self {
+        GithubError::Request(err)
+    }
+}
+
+async fn ok_or_status_error(response: reqwest::Response) -> Result<reqwest::Response, GithubError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let detail = response.text().await.unwrap_or_default();
+    Err(GithubError::Status(status, detail))
+}
+
+/// Marks a single notification thread as read.
+///
+/// `PATCH /notifications/threads/{thread_id}`
+pub async fn mark_thread_read(client: &Client, token: &str, thread_id: &str) -> Result<(), GithubError> {
+    let url = format!("{NOTIFICATIONS_URL}/threads/{thread_id}");
+    let response = client
+        .patch(url)
+        .header(USER_AGENT, "Rust Reqwest")
+        .header(AUTHORIZATION, format!("Bearer {token}"))
+        .header(ACCEPT, "application/vnd.github+json")
+        .send()
+        .await?;
+    ok_or_status_error(response).await?;
+    Ok(())
+}
+
+/// Marks every notification up to now as read.
+///
+/// `PUT /notifications` with an empty JSON body; `last_read_at` is not sent.
+pub async fn mark_all_read(client: &Client, token: &str) -> Result<(), GithubError> {
+    let response = client
+        .put(NOTIFICATIONS_URL)
+        .header(USER_AGENT, "Rust Reqwest")
+        .header(AUTHORIZATION, format!("Bearer {token}"))
+        .header(ACCEPT, "application/vnd.github+json")
+        // omitting `last_read_at` tells GitHub to use the current time
+        .json(&serde_json::json!({}))
+        .send()
+        .await?;
+    ok_or_status_error(response).await?;
+    Ok(())
+}
+
+/// Fetches the subscription state for a single notification thread.
+///
+/// `GET /notifications/threads/{thread_id}/subscription`
+pub async fn get_thread_subscription(
+    client: &Client,
+    token: &str,
+    thread_id: &str,
+) -> Result<ThreadSubscription, GithubError> {
+    let url = format!("{NOTIFICATIONS_URL}/threads/{thread_id}/subscription");
+    let response = client
+        .get(url)
+        .header(USER_AGENT, "Rust Reqwest")
+        .header(AUTHORIZATION, format!("Bearer {token}"))
+        .header(ACCEPT, "application/vnd.github+json")
+        .send()
+        .await?;
+    let response = ok_or_status_error(response).await?;
+    Ok(response.json::<ThreadSubscription>().await?)
+}
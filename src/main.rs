@@ -1,13 +1,19 @@
 use std::{env, fs, process};
+use std::time::Duration;
 
-use reqwest::header::{ACCEPT, AUTHORIZATION, USER_AGENT};
+use reqwest::header::{ACCEPT, AUTHORIZATION, HeaderValue, IF_MODIFIED_SINCE, LAST_MODIFIED, USER_AGENT};
 use reqwest::{Client, Error};
 use serde::Deserialize;
 
-use notify::{notify, NotificationParamsBuilder};
-
+pub mod db;
+pub mod github;
+pub mod notifier;
 pub mod util;
+pub mod webhook;
+
+use db::DbCtx;
 
+use notifier::{dispatch, load_notifiers};
 use util::*;
 
 const REQUEST_URL: &str = "https://api.github.com/notifications";
@@ -27,13 +33,52 @@ struct Notification {
     updated_at: String,
 }
 
+/// Everything that can go wrong in a single `poll_once` call. Kept
+/// separate from `reqwest::Error` so a bad response status or a database
+/// hiccup doesn't have to masquerade as a network error, and so the
+/// `--watch` loop can log a failed poll and try again instead of the
+/// process exiting outright.
+#[derive(Debug)]
+enum PollError {
+    Request(Error),
+    Status(reqwest::StatusCode, String),
+    Db(rusqlite::Error),
+}
+
+impl std::fmt::Display for PollError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PollError::Request(err) => write!(f, "{err}"),
+            PollError::Status(status, detail) => write!(f, "{status} {detail}"),
+            PollError::Db(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<Error> for PollError {
+    fn from(err: Error) -> Self {
+        PollError::Request(err)
+    }
+}
+
+impl From<rusqlite::Error> for PollError {
+    fn from(err: rusqlite::Error) -> Self {
+        PollError::Db(err)
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     // handle command line arguments
-    if parse_args() {
-        return Ok(());
-    }
-    // else if no arguments used, proceed with default actions:
+    let command = match parse_args() {
+        Command::Handled => return Ok(()),
+        Command::Serve(addr) => {
+            let notifiers = load_notifiers(&get_notifier_config_path());
+            webhook::serve(&addr, notifiers).await;
+            return Ok(());
+        }
+        command => command,
+    };
 
     // get token from environment variable
     let token = match env::var(ENV_VAR_NAME) {
@@ -46,99 +91,156 @@ async fn main() -> Result<(), Error> {
         }
     };
 
-    // get or create local persistence file to save notification ids already shown
-    let ids_file_path = get_persistence_file_path();
-
-    // make request to GH notifications API
     let client = Client::new();
-    let response = match client
+
+    // thread subcommands (mark read, mark all read, show subscription) run
+    // once and return; otherwise poll once, or forever with --watch
+    match command {
+        Command::MarkRead(thread_id) => {
+            match github::mark_thread_read(&client, &token, &thread_id).await {
+                Ok(()) => println!("marked thread {thread_id} as read"),
+                Err(err) => {
+                    println!("failed to mark thread {thread_id} as read: {err}");
+                    process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+        Command::MarkAllRead => {
+            match github::mark_all_read(&client, &token).await {
+                Ok(()) => println!("marked all notifications as read"),
+                Err(err) => {
+                    println!("failed to mark all notifications as read: {err}");
+                    process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+        Command::ThreadSubscription(thread_id) => {
+            match github::get_thread_subscription(&client, &token, &thread_id).await {
+                Ok(subscription) => println!("{:#?}", subscription),
+                Err(err) => {
+                    println!("failed to fetch subscription for thread {thread_id}: {err}");
+                    process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+        Command::Watch => loop {
+            let poll_interval = match poll_once(&client, &token).await {
+                Ok(poll_interval) => poll_interval,
+                Err(err) => {
+                    // a transient hiccup (rate limit, 5xx, dropped connection, a
+                    // locked database, ...) shouldn't kill a long-running daemon
+                    println!("poll failed, will retry: {err}");
+                    DEFAULT_POLL_INTERVAL_SECS
+                }
+            };
+            tokio::time::sleep(Duration::from_secs(poll_interval)).await;
+        },
+        _ => {
+            if let Err(err) = poll_once(&client, &token).await {
+                notify_connection_error(&format!("{err}"));
+                println!("{}", err);
+                process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Polls the notifications API once, displaying notifications for anything
+/// new, and returns the number of seconds the caller should wait before
+/// polling again (from `X-Poll-Interval`, or a sensible default).
+async fn poll_once(client: &Client, token: &str) -> Result<u64, PollError> {
+    // open (or create) the SQLite database tracking which threads we've already notified about
+    let db = DbCtx::open(&get_db_path())?;
+    let last_modified_file_path = get_last_modified_file_path();
+
+    // conditionally request: send back the Last-Modified value we saw last
+    // time so GitHub can reply 304 Not Modified if nothing has changed
+    let previous_last_modified = fs::read_to_string(&last_modified_file_path).ok();
+
+    let mut request = client
         .get(REQUEST_URL)
         .header(USER_AGENT, "Rust Reqwest")
         .header(AUTHORIZATION, format!("Bearer {token}"))
-        .header(ACCEPT, "application/vnd.github+json")
-        .send()
-        .await
-    {
-        Ok(response) => response,
-        Err(err) => {
-            notify_connection_error(&format!("{err}"));
-            println!("{}", err);
-            process::exit(1);
+        .header(ACCEPT, "application/vnd.github+json");
+    if let Some(last_modified) = &previous_last_modified {
+        if let Ok(value) = HeaderValue::from_str(last_modified) {
+            request = request.header(IF_MODIFIED_SINCE, value);
         }
-    };
+    }
 
-    // handle unsuccessful responses
+    // make request to GH notifications API
+    let response = request.send().await?;
+
+    // the X-Poll-Interval header tells us the minimum time to wait before polling again
+    let poll_interval = response
+        .headers()
+        .get("x-poll-interval")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_POLL_INTERVAL_SECS);
+
+    // nothing has changed since our last poll; nothing to process
     let status = response.status();
+    if status == 304 {
+        return Ok(poll_interval);
+    }
+
+    // handle unsuccessful responses
     if status != 200 {
         let text = response.text().await?;
         let detail = text.split(' ').collect::<String>();
-        notify_connection_error(&format!("{status} {detail}"));
-        println!("Error response: {} {}", status, text);
-        process::exit(1);
+        return Err(PollError::Status(status, detail));
     };
 
-    // read already notified ids from file
-    let read_ids_str = match fs::read_to_string(&ids_file_path) {
-        Ok(ids) => ids,
-        _ => "".to_string(),
-    };
-    let read_id_strs = read_ids_str.split(",").collect::<Vec<&str>>();
+    // remember the Last-Modified value for the next poll
+    if let Some(last_modified) = response.headers().get(LAST_MODIFIED) {
+        if let Ok(value) = last_modified.to_str() {
+            let _ = fs::write(&last_modified_file_path, value);
+        }
+    }
+
+    // load the configured notifier backends (desktop, email, ...)
+    let notifiers = load_notifiers(&get_notifier_config_path());
 
     // handle successful API response
     let response_json: Vec<Notification> = response.json().await?;
 
-    // loop through notifications in response, checking against saved notification ids
-    // and display desktop notification if identifier not already saved to file
-    let mut new_ids: Vec<String> = Vec::new();
+    // loop through notifications in response, checking against the database
+    // and display a notification if we haven't already notified about this
+    // thread at this (or a later) `updated_at`
     for notification in &response_json {
-        let mut identifier: String = notification.id.to_owned();
-        identifier.push_str(&notification.updated_at);
-        let check = identifier.clone();
-        new_ids.push(identifier);
-        if read_id_strs.contains(&check.as_str()) {
-            // have already notified about this notification
-            continue;
+        match db.seen(&notification.id, &notification.updated_at) {
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(err) => {
+                dbg!(err);
+                continue;
+            }
         }
 
         // build notification parts
         let message = &notification.subject.title;
         let optional_url = notification.subject.url.clone();
-        let onclick_url = build_pull_or_issue_url(optional_url);
         let reason_vec = &notification.reason.split("_").collect::<Vec<&str>>();
         let subtitle = reason_vec.join(" ");
 
-        // display notification
-        match NotificationParamsBuilder::default()
-            .title("New Github Notification")
-            .subtitle(subtitle.as_str())
-            .message(message.as_str())
-            .open(onclick_url.as_str())
-            .build()
-        {
-            Ok(params) => notify(&params),
-            Err(err) => {
-                dbg!(err);
-            }
+        // fan the notification out to every configured backend
+        dispatch(&notifiers, subtitle.as_str(), message.as_str(), optional_url);
+
+        if let Err(err) = db.record(
+            &notification.id,
+            &notification.updated_at,
+            &notification.reason,
+            message,
+            &now_timestamp(),
+        ) {
+            dbg!(err);
         }
     }
 
-    // save notified IDs to file system
-    let ids_len = new_ids.len();
-    if ids_len == 1 {
-        match fs::write(&ids_file_path, &new_ids[0]) {
-            Ok(_) => (),
-            Err(err) => {
-                dbg!(err);
-            }
-        }
-    } else if ids_len > 1 {
-        let ids_to_write: String = new_ids.join(",");
-        match fs::write(&ids_file_path, ids_to_write) {
-            Ok(_) => (),
-            Err(err) => {
-                dbg!(err);
-            }
-        }
-    }
-    Ok(())
+    Ok(poll_interval)
 }
@@ -0,0 +1,160 @@
+use std::fs;
+use std::path::Path;
+
+use lettre::message::Message;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{SmtpTransport, Transport};
+use notify::{notify, NotificationParamsBuilder};
+use serde::Deserialize;
+
+use crate::util::build_pull_or_issue_url;
+
+/// A single configured notification backend, as read from the notifier
+/// config file. Several variants can be active at once: every entry in
+/// `backends` fires for each new GitHub notification.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotifierConfig {
+    Desktop,
+    Email {
+        username: String,
+        password: String,
+        mailserver: String,
+        from: String,
+        to: String,
+    },
+}
+
+/// The full notifier config file: a list of backends to fan a
+/// notification out to.
+#[derive(Deserialize)]
+pub struct NotifierSettings {
+    pub backends: Vec<NotifierConfig>,
+}
+
+impl NotifierConfig {
+    /// Builds the concrete `Notifier` this config entry describes.
+    pub fn build(self) -> Box<dyn Notifier> {
+        match self {
+            NotifierConfig::Desktop => Box::new(DesktopNotifier),
+            NotifierConfig::Email {
+                username,
+                password,
+                mailserver,
+                from,
+                to,
+            } => Box::new(EmailNotifier {
+                username,
+                password,
+                mailserver,
+                from,
+                to,
+            }),
+        }
+    }
+}
+
+/// A destination a new GitHub notification can be routed to.
+pub trait Notifier: Send + Sync {
+    fn send(&self, title: &str, subtitle: &str, message: &str, url: &str);
+}
+
+/// The existing behavior: a native macOS desktop notification.
+pub struct DesktopNotifier;
+
+impl Notifier for DesktopNotifier {
+    fn send(&self, title: &str, subtitle: &str, message: &str, url: &str) {
+        match NotificationParamsBuilder::default()
+            .title(title)
+            .subtitle(subtitle)
+            .message(message)
+            .open(url)
+            .build()
+        {
+            Ok(params) => notify(&params),
+            Err(err) => {
+                dbg!(err);
+            }
+        }
+    }
+}
+
+/// Delivers notifications as email, over an authenticated SMTP
+/// connection with STARTTLS.
+pub struct EmailNotifier {
+    username: String,
+    password: String,
+    mailserver: String,
+    from: String,
+    to: String,
+}
+
+impl Notifier for EmailNotifier {
+    fn send(&self, title: &str, subtitle: &str, message: &str, url: &str) {
+        let body = format!("{subtitle}\n\n{message}\n\n{url}");
+        let from = match self.from.parse() {
+            Ok(from) => from,
+            Err(err) => {
+                dbg!(err);
+                return;
+            }
+        };
+        let to = match self.to.parse() {
+            Ok(to) => to,
+            Err(err) => {
+                dbg!(err);
+                return;
+            }
+        };
+        let email = match Message::builder()
+            .from(from)
+            .to(to)
+            .subject(title)
+            .body(body)
+        {
+            Ok(email) => email,
+            Err(err) => {
+                dbg!(err);
+                return;
+            }
+        };
+
+        let creds = Credentials::new(self.username.clone(), self.password.clone());
+        let mailer = match SmtpTransport::starttls_relay(&self.mailserver) {
+            Ok(mailer) => mailer.credentials(creds).build(),
+            Err(err) => {
+                dbg!(err);
+                return;
+            }
+        };
+
+        if let Err(err) = mailer.send(&email) {
+            dbg!(err);
+        }
+    }
+}
+
+/// Loads the notifier backends from a JSON config file, falling back to
+/// desktop-only notifications if the file is missing or invalid.
+pub fn load_notifiers(config_path: &Path) -> Vec<Box<dyn Notifier>> {
+    let contents = match fs::read_to_string(config_path) {
+        Ok(contents) => contents,
+        Err(_) => return vec![Box::new(DesktopNotifier)],
+    };
+
+    match serde_json::from_str::<NotifierSettings>(&contents) {
+        Ok(settings) => settings.backends.into_iter().map(NotifierConfig::build).collect(),
+        Err(err) => {
+            dbg!(err);
+            vec![Box::new(DesktopNotifier)]
+        }
+    }
+}
+
+/// Fans a new GitHub notification out to every configured backend.
+pub fn dispatch(notifiers: &[Box<dyn Notifier>], subtitle: &str, message: &str, optional_url: Option<String>) {
+    let url = build_pull_or_issue_url(optional_url);
+    for notifier in notifiers {
+        notifier.send("New Github Notification", subtitle, message, &url);
+    }
+}
@@ -0,0 +1,150 @@
+use std::env;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use notify::{notify, NotificationParamsBuilder};
+
+const DB_FILE_NAME: &str = ".gh_notifier.sqlite3";
+const LAST_MODIFIED_FILE_NAME: &str = ".gh_notifier_last_modified";
+const NOTIFIER_CONFIG_FILE_NAME: &str = ".gh_notifier_config.json";
+
+/// Default time (in seconds) to wait between polls when the API response
+/// doesn't include an `X-Poll-Interval` header.
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 60;
+
+/// The action the user asked for on the command line.
+pub enum Command {
+    /// Fall through to the default "poll once" behavior.
+    Poll,
+    /// `--help` / `-h` was handled already, or a flag was missing its
+    /// argument and the error was printed; exit immediately.
+    Handled,
+    /// `--mark-read <thread_id>`: mark a single thread as read.
+    MarkRead(String),
+    /// `--mark-all-read`: mark every notification as read.
+    MarkAllRead,
+    /// `--thread-subscription <thread_id>`: show a thread's subscription state.
+    ThreadSubscription(String),
+    /// `--watch`: poll forever instead of exiting after one run.
+    Watch,
+    /// `--serve <addr>`: run a webhook receiver instead of polling.
+    Serve(String),
+}
+
+/// Parses CLI arguments for the flags this tool understands.
+pub fn parse_args() -> Command {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        return Command::Poll;
+    }
+
+    match args[1].as_str() {
+        "--help" | "-h" => {
+            println!("gh-notifier: a small GitHub notifications watcher");
+            println!("usage: gh-notifier [--help]");
+            println!("       gh-notifier --mark-read <thread_id>");
+            println!("       gh-notifier --mark-all-read");
+            println!("       gh-notifier --thread-subscription <thread_id>");
+            println!("       gh-notifier --watch");
+            println!("       gh-notifier --serve <addr>");
+            Command::Handled
+        }
+        "--mark-read" => match args.get(2) {
+            Some(thread_id) => Command::MarkRead(thread_id.to_owned()),
+            None => {
+                println!("--mark-read requires a <thread_id> argument");
+                Command::Handled
+            }
+        },
+        "--mark-all-read" => Command::MarkAllRead,
+        "--watch" => Command::Watch,
+        "--serve" => match args.get(2) {
+            Some(addr) => Command::Serve(addr.to_owned()),
+            None => {
+                println!("--serve requires an <addr> argument, e.g. 127.0.0.1:8080");
+                Command::Handled
+            }
+        },
+        "--thread-subscription" => match args.get(2) {
+            Some(thread_id) => Command::ThreadSubscription(thread_id.to_owned()),
+            None => {
+                println!("--thread-subscription requires a <thread_id> argument");
+                Command::Handled
+            }
+        },
+        _ => Command::Poll,
+    }
+}
+
+/// Returns the path to the SQLite database used to persist which
+/// notification threads we've already alerted on.
+pub fn get_db_path() -> PathBuf {
+    let mut path = dirs_home();
+    path.push(DB_FILE_NAME);
+    path
+}
+
+/// Returns the path to the file used to persist the `Last-Modified` value
+/// from the previous poll, for conditional `If-Modified-Since` requests.
+pub fn get_last_modified_file_path() -> PathBuf {
+    let mut path = dirs_home();
+    path.push(LAST_MODIFIED_FILE_NAME);
+    path
+}
+
+/// Returns the path to the JSON file describing which notifier backends
+/// (desktop, email, ...) are active.
+pub fn get_notifier_config_path() -> PathBuf {
+    let mut path = dirs_home();
+    path.push(NOTIFIER_CONFIG_FILE_NAME);
+    path
+}
+
+fn dirs_home() -> PathBuf {
+    env::var("HOME").map(PathBuf::from).unwrap_or_else(|_| PathBuf::from("."))
+}
+
+/// Builds the URL that should be opened when a notification is clicked,
+/// rewriting the GitHub API URL (if present) into the equivalent
+/// browser-facing `github.com` URL.
+pub fn build_pull_or_issue_url(api_url: Option<String>) -> String {
+    match api_url {
+        Some(url) => url
+            .replace("api.github.com/repos", "github.com")
+            .replace("/pulls/", "/pull/"),
+        None => "https://github.com/notifications".to_string(),
+    }
+}
+
+/// Shows a desktop notification reporting a configuration error.
+pub fn notify_error(message: &str) {
+    notify_simple("Github Notifier Error", message);
+}
+
+/// Shows a desktop notification reporting a network/connection error.
+pub fn notify_connection_error(message: &str) {
+    notify_simple("Github Notifier Connection Error", message);
+}
+
+/// Returns the current Unix timestamp as a string, used to stamp
+/// `first_seen`/`last_notified` columns in the persistence database.
+pub fn now_timestamp() -> String {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+        .to_string()
+}
+
+fn notify_simple(title: &str, message: &str) {
+    match NotificationParamsBuilder::default()
+        .title(title)
+        .message(message)
+        .build()
+    {
+        Ok(params) => notify(&params),
+        Err(err) => {
+            dbg!(err);
+        }
+    }
+}
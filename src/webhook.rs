@@ -0,0 +1,232 @@
+use std::env;
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+use crate::notifier::{dispatch, Notifier};
+
+const WEBHOOK_SECRET_ENV_VAR_NAME: &str = "GH_NOTIFIER_WEBHOOK_SECRET";
+const SIGNATURE_HEADER: &str = "x-hub-signature-256";
+const EVENT_HEADER: &str = "x-github-event";
+
+type HmacSha256 = Hmac<Sha256>;
+
+struct WebhookState {
+    secret: String,
+    notifiers: Vec<Box<dyn Notifier>>,
+}
+
+/// The subset of a webhook delivery's payload we need to build a
+/// notification: the repo it's about and the subject (PR/issue/commit)
+/// that triggered it.
+#[derive(Deserialize)]
+struct WebhookPayload {
+    #[serde(default)]
+    repository: Option<WebhookRepository>,
+    #[serde(default)]
+    pull_request: Option<WebhookSubject>,
+    #[serde(default)]
+    issue: Option<WebhookSubject>,
+    #[serde(default)]
+    head_commit: Option<WebhookCommit>,
+}
+
+#[derive(Deserialize)]
+struct WebhookRepository {
+    full_name: String,
+}
+
+#[derive(Deserialize)]
+struct WebhookSubject {
+    title: String,
+    html_url: String,
+}
+
+#[derive(Deserialize)]
+struct WebhookCommit {
+    message: String,
+    url: String,
+}
+
+/// Runs an HTTP server at `addr` that receives GitHub webhook deliveries
+/// and fires notifications immediately, as an alternative to polling.
+pub async fn serve(addr: &str, notifiers: Vec<Box<dyn Notifier>>) {
+    let secret = env::var(WEBHOOK_SECRET_ENV_VAR_NAME).unwrap_or_else(|_| {
+        eprintln!("warning: {WEBHOOK_SECRET_ENV_VAR_NAME} is not set; all deliveries will be rejected");
+        String::new()
+    });
+
+    let state = Arc::new(WebhookState { secret, notifiers });
+    let app = Router::new().route("/", post(receive)).with_state(state);
+
+    println!("listening for GitHub webhook deliveries on {addr}");
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .expect("failed to bind webhook listener");
+    axum::serve(listener, app).await.expect("webhook server failed");
+}
+
+async fn receive(
+    State(state): State<Arc<WebhookState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    if state.secret.is_empty() {
+        // no secret configured: reject everything rather than verify against
+        // a publicly-computable empty key
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let signature = match headers.get(SIGNATURE_HEADER).and_then(|v| v.to_str().ok()) {
+        Some(signature) => signature,
+        None => return StatusCode::UNAUTHORIZED,
+    };
+
+    if !verify_signature(&state.secret, &body, signature) {
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let event = headers
+        .get(EVENT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("unknown")
+        .to_string();
+
+    let payload: WebhookPayload = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(_) => return StatusCode::BAD_REQUEST,
+    };
+
+    if let Some((message, url)) = webhook_subject(&payload) {
+        let repo = payload
+            .repository
+            .map(|repository| repository.full_name)
+            .unwrap_or_else(|| "unknown repository".to_string());
+        let subtitle = format!("{} ({})", event.replace('_', " "), repo);
+        dispatch(&state.notifiers, &subtitle, &message, Some(url));
+    }
+
+    StatusCode::OK
+}
+
+fn webhook_subject(payload: &WebhookPayload) -> Option<(String, String)> {
+    if let Some(pull_request) = &payload.pull_request {
+        return Some((pull_request.title.clone(), pull_request.html_url.clone()));
+    }
+    if let Some(issue) = &payload.issue {
+        return Some((issue.title.clone(), issue.html_url.clone()));
+    }
+    if let Some(commit) = &payload.head_commit {
+        return Some((commit.message.clone(), commit.url.clone()));
+    }
+    None
+}
+
+/// Verifies a `X-Hub-Signature-256` header against the raw request body,
+/// using a constant-time comparison to avoid leaking timing information.
+fn verify_signature(secret: &str, body: &[u8], signature_header: &str) -> bool {
+    let expected_hex = match signature_header.strip_prefix("sha256=") {
+        Some(hex) => hex,
+        None => return false,
+    };
+    let expected = match hex_decode(expected_hex) {
+        Some(bytes) => bytes,
+        None => return false,
+    };
+
+    let mut mac = match HmacSha256::new_from_slice(secret.as_bytes()) {
+        Ok(mac) => mac,
+        Err(_) => return false,
+    };
+    mac.update(body);
+    let computed = mac.finalize().into_bytes();
+
+    computed.ct_eq(&expected).into()
+}
+
+fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        let bytes = mac.finalize().into_bytes();
+        let mut hex = String::with_capacity(bytes.len() * 2);
+        for byte in bytes {
+            hex.push_str(&format!("{byte:02x}"));
+        }
+        format!("sha256={hex}")
+    }
+
+    #[test]
+    fn accepts_a_valid_signature() {
+        let secret = "it's a secret to everybody";
+        let body = b"{\"zen\":\"Responsive is better than fast.\"}";
+        let signature = sign(secret, body);
+
+        assert!(verify_signature(secret, body, &signature));
+    }
+
+    #[test]
+    fn rejects_a_tampered_body() {
+        let secret = "it's a secret to everybody";
+        let signature = sign(secret, b"original body");
+
+        assert!(!verify_signature(secret, b"tampered body", &signature));
+    }
+
+    #[test]
+    fn rejects_a_tampered_signature() {
+        let secret = "it's a secret to everybody";
+        let body = b"some body";
+        let mut signature = sign(secret, body);
+        let last = signature.pop().unwrap();
+        signature.push(if last == '0' { '1' } else { '0' });
+
+        assert!(!verify_signature(secret, body, &signature));
+    }
+
+    #[test]
+    fn rejects_a_missing_sha256_prefix() {
+        let secret = "it's a secret to everybody";
+        let body = b"some body";
+        let signature = sign(secret, body);
+        let bare_hex = signature.strip_prefix("sha256=").unwrap();
+
+        assert!(!verify_signature(secret, body, bare_hex));
+    }
+
+    #[test]
+    fn rejects_odd_length_hex() {
+        assert_eq!(hex_decode("abc"), None);
+    }
+
+    #[test]
+    fn rejects_non_hex_characters() {
+        assert_eq!(hex_decode("zz"), None);
+    }
+
+    #[test]
+    fn decodes_valid_hex() {
+        assert_eq!(hex_decode("48656c6c6f"), Some(b"Hello".to_vec()));
+    }
+}